@@ -3,7 +3,7 @@ use std::str::Chars;
 
 use unicode_xid::UnicodeXID;
 
-use super::unescape::unescape;
+use super::unescape::{unescape, UnescapeError};
 
 impl Lexer<'_> {
     pub fn new(code: &str) -> Lexer {
@@ -13,11 +13,33 @@ impl Lexer<'_> {
             line: 1,
             column: 0,
             current: '\0',
+            bytes: 0,
+            start: 0,
+            trivia: false,
         }
     }
 
+    /// Build a lexer in comment-preserving mode. Comments are no longer
+    /// silently eaten: each is scanned as a
+    /// [`TokenType::LineComment`]/[`TokenType::BlockComment`] and attached to
+    /// the following token as leading trivia, with same-line comments trailing
+    /// the preceding token (the way rustc and boa carry trivia). Whitespace is
+    /// still skipped; a blank line before a comment is kept only as that
+    /// comment's `isolated` flag. The default [`Lexer::new`] behavior is
+    /// unchanged.
+    pub fn new_with_trivia(code: &str) -> Lexer {
+        let mut lexer = Lexer::new(code);
+        lexer.trivia = true;
+        lexer
+    }
+
     /// Generate next token from the source code.
     pub fn next_token(&mut self) -> Token {
+        if self.trivia {
+            return self.next_token_with_trivia();
+        }
+
+        self.start = self.bytes;
         if self.eof() { return self.make_token(TokenType::Eof, ""); }
 
         let op_first = [
@@ -58,30 +80,36 @@ impl Lexer<'_> {
                 self.next_token()
             }
             
-            '\'' => { 
+            '\'' => {
                 let mut content = String::new();
                 let (ln, col) = (self.line, self.column);
                 while self.lookahead() != '\'' && !self.eof() {
                     content.push(self.next().unwrap());
                 }
-                
-                if !self.eof() { self.next().unwrap(); }
-                
-                let text = unescape(&content);
-                if let Err(e) = text {
-                    return self.make_token(TokenType::Error, &e.to_string());
+
+                if self.eof() {
+                    return self.error_token(LexErrorKind::UnterminatedChar);
                 }
+                self.next().unwrap();
+
+                let text = match unescape(&content) {
+                    Ok(text) => text,
+                    Err((e, off)) => return self.bad_escape(e, off),
+                };
 
-                let text = text.unwrap();
-                
                 Token {
                     ty: TokenType::CharLiteral,
                     text,
                     line: ln,
                     column: col,
+                    start: self.start,
+                    len: self.bytes - self.start,
+                    leading: Vec::new(),
+                    trailing: Vec::new(),
+                    isolated: false,
                 }
             }
-            
+
             '\"' => {
                 let mut content = String::new();
                 let (ln, col) = (self.line, self.column);
@@ -89,20 +117,26 @@ impl Lexer<'_> {
                     content.push(self.next().unwrap());
                 }
 
-                if !self.eof() { self.next().unwrap(); }
-                
-                let text = unescape(&content);
-                if let Err(e) = text {
-                    return self.make_token(TokenType::Error, &e.to_string());
+                if self.eof() {
+                    return self.error_token(LexErrorKind::UnterminatedString);
                 }
+                self.next().unwrap();
 
-                let text = text.unwrap();
+                let text = match unescape(&content) {
+                    Ok(text) => text,
+                    Err((e, off)) => return self.bad_escape(e, off),
+                };
 
                 Token {
                     ty: TokenType::StringLiteral,
                     text,
                     line: ln,
                     column: col,
+                    start: self.start,
+                    len: self.bytes - self.start,
+                    leading: Vec::new(),
+                    trailing: Vec::new(),
+                    isolated: false,
                 }
             }
             
@@ -136,49 +170,7 @@ impl Lexer<'_> {
                 self.make_token(TokenType::Id, &id)
             }
             
-            c @ '0'..='9' => {
-                if c == '0' {
-                    match self.lookahead() {
-                        'b' => {
-                            let val = self.number("0b");
-                            self.make_token(TokenType::IntLiteral, &val)
-                        }
-
-                        'o' => {
-                            let val = self.number("0o");
-                            self.make_token(TokenType::IntLiteral, &val)
-                        }
-
-                        'x' => {
-                            let val = self.number("0x");
-                            self.make_token(TokenType::IntLiteral, &val)
-                        }
-
-                        '.' => {
-                            let val = self.float("0.");
-                            self.make_token(TokenType::FloatLiteral, &val)
-                        }
-
-                        n @ '0'..='9' => {
-                            let mut lit = String::from("0"); lit.push(n);
-                            let val = self.number(&lit);
-                            self.make_token(TokenType::IntLiteral, &val)
-                        }
-
-                        _ => {
-                            // If it is not a valid number, return an error token.
-                            self.make_token(TokenType::Error, "Invalid number literal suffix")
-                        }
-                    }
-                } else {
-                    if self.lookahead() == '.' {
-                        let lit = self.float(&c.to_string());
-                        return self.make_token(TokenType::FloatLiteral, &lit);
-                    } 
-                    let lit = self.number(&c.to_string());
-                    self.make_token(TokenType::IntLiteral, &lit)
-                }
-            }
+            c @ '0'..='9' => self.scan_number(c),
             
             c if op_first.contains(&c) => {
                 let mut op = String::new();
@@ -199,7 +191,7 @@ impl Lexer<'_> {
             '{' => self.make_token(TokenType::LBrace, "{"),
             '}' => self.make_token(TokenType::RBrace, "}"),
             
-            _ => self.make_token(TokenType::Error, "Invalid character"),
+            _ => self.error_token(LexErrorKind::UnknownChar),
         }
     }
 
@@ -207,55 +199,128 @@ impl Lexer<'_> {
         self.src.as_str().is_empty()
     }
     
-    fn number(&mut self, lit: &str) -> String {
-        let mut content = String::from(lit);
-        
-        match lit {
-            "0b" => {
-                self.next();
-                while let c @ '0'..='1' = self.lookahead() {
-                    content.push(c);
-                    self.next();
-                }
+    /// Scan a numeric literal starting from its already-consumed first digit
+    /// `c`, following the DFA
+    ///
+    /// ```text
+    /// int      [0-9][0-9_]*
+    /// fraction . [0-9_]*
+    /// exponent (e|E) (+|-)? [0-9][0-9_]*
+    /// ```
+    ///
+    /// plus the base-prefixed forms `0b`/`0o`/`0x`. Underscores act as digit
+    /// separators and are stripped from the stored text; a leading, trailing,
+    /// or doubled separator makes the literal an [`TokenType::Error`] token
+    /// that ends at the offending separator, and scanning resumes there.
+    /// The token is a [`TokenType::FloatLiteral`] iff it carries a fraction or
+    /// an exponent, otherwise an [`TokenType::IntLiteral`].
+    fn scan_number(&mut self, c: char) -> Token {
+        let mut text = String::new();
+        text.push(c);
+        let mut is_float = false;
+
+        if c == '0' && matches!(self.lookahead(), 'b' | 'o' | 'x') {
+            let base = self.lookahead();
+            self.next();
+            text.push(base);
+
+            let valid: fn(char) -> bool = match base {
+                'b' => |c| matches!(c, '0'..='1'),
+                'o' => |c| matches!(c, '0'..='7'),
+                _ => |c: char| c.is_ascii_hexdigit(),
+            };
+
+            match self.digits(false, valid) {
+                Some(d) if !d.is_empty() => text.push_str(&d),
+                _ => return self.error_token(LexErrorKind::InvalidNumber),
             }
-            
-            "0o" => {
+        } else {
+            // Integer part: a digit already precedes, so a separator may follow
+            // immediately.
+            match self.digits(true, |c| c.is_ascii_digit()) {
+                Some(d) => text.push_str(&d),
+                None => return self.error_token(LexErrorKind::InvalidNumber),
+            }
+
+            // Optional fraction.
+            if self.lookahead() == '.' {
+                is_float = true;
                 self.next();
-                while let c @ '0'..='7' = self.lookahead() {
-                    content.push(c);
-                    self.next();
+                text.push('.');
+                match self.digits(false, |c| c.is_ascii_digit()) {
+                    Some(d) => text.push_str(&d),
+                    None => return self.error_token(LexErrorKind::InvalidNumber),
                 }
             }
-            
-            "0x" => {
+
+            // Optional exponent.
+            if matches!(self.lookahead(), 'e' | 'E') {
+                is_float = true;
                 self.next();
-                while self.lookahead().is_ascii_hexdigit() {
+                text.push('e');
+                if matches!(self.lookahead(), '+' | '-') {
+                    text.push(self.lookahead());
                     self.next();
-                    content.push(self.curr());
                 }
-            }
-            
-            _ => {
-                while let _c @ '0'..='9' = self.lookahead() {
-                    content.push(self.next().unwrap());
+                match self.digits(false, |c| c.is_ascii_digit()) {
+                    Some(d) if !d.is_empty() => text.push_str(&d),
+                    _ => return self.error_token(LexErrorKind::InvalidNumber),
                 }
             }
         }
-        
-        content
-    }
-    
-    fn float(&mut self, lit: &str) -> String {
-        self.next();
-        let mut content = String::from(lit);
-        
-        // Read the integer part.
-        while let c @ '0'..='9' = self.lookahead() {
-            content.push(c);
+
+        // An `n` suffix promotes an integer literal to a big-int. It is only
+        // valid after an integer part (in any base) — never after a fraction
+        // or exponent.
+        if self.lookahead() == 'n' {
             self.next();
+            if is_float {
+                return self.error_token(LexErrorKind::InvalidNumberSuffix);
+            }
+            return self.make_token(TokenType::BigIntLiteral, &text);
         }
-        
-        content
+
+        let ty = if is_float { TokenType::FloatLiteral } else { TokenType::IntLiteral };
+        self.make_token(ty, &text)
+    }
+
+    /// Read a run of digits accepted by `valid`, with `_` separators stripped
+    /// from the result. `preceded` states whether a digit already sits to the
+    /// left of this run (so a leading separator is legal). A doubled or
+    /// trailing separator yields `None`; an empty run yields `Some("")`.
+    fn digits(&mut self, preceded: bool, valid: impl Fn(char) -> bool) -> Option<String> {
+        let mut out = String::new();
+        let mut prev_sep = !preceded;
+        let mut consumed = false;
+
+        loop {
+            let c = self.lookahead();
+            if c == '_' {
+                if prev_sep {
+                    return None;
+                }
+                prev_sep = true;
+                consumed = true;
+                self.next();
+            } else if valid(c) {
+                out.push(c);
+                prev_sep = false;
+                consumed = true;
+                self.next();
+            } else {
+                break;
+            }
+        }
+
+        // A run that ends on a separator has a trailing `_`. Guarding on
+        // `consumed` (any digit *or* separator taken here) rather than on a
+        // digit keeps an empty run — e.g. the fraction of `1.` — legal while
+        // still rejecting `1_`, where `preceded` supplies the digit to the left.
+        if prev_sep && consumed {
+            return None;
+        }
+
+        Some(out)
     }
     
     fn eat_until(&mut self, mut f: impl FnMut(char) -> bool) {
@@ -269,12 +334,162 @@ impl Lexer<'_> {
         self.src.clone().next().unwrap_or('\0')
     }
 
+    /// Get the char one past [`Lexer::lookahead`] without consuming anything.
+    fn lookahead2(&self) -> char {
+        let mut it = self.src.clone();
+        it.next();
+        it.next().unwrap_or('\0')
+    }
+
+    /// Scan the next token together with its surrounding trivia (only reached
+    /// in comment-preserving mode). Leading comments are collected onto the
+    /// produced token; any same-line comment after it becomes trailing trivia.
+    fn next_token_with_trivia(&mut self) -> Token {
+        let leading = self.collect_leading_trivia();
+
+        // Scan the core token with the normal machinery; trivia has already
+        // been consumed, so the cursor sits on the token's first character.
+        self.trivia = false;
+        let mut tok = self.next_token();
+        self.trivia = true;
+
+        tok.leading = leading;
+        tok.trailing = self.collect_trailing_trivia();
+        tok
+    }
+
+    /// Consume whitespace and comments up to the next real token, returning
+    /// the comments as trivia tokens. A comment preceded by a blank line is
+    /// flagged `isolated` so a pretty-printer can reconstruct the spacing.
+    fn collect_leading_trivia(&mut self) -> Vec<Token> {
+        let mut out = Vec::new();
+        let mut newlines = 0usize;
+
+        loop {
+            let c = self.lookahead();
+            if c == '\0' {
+                break;
+            }
+
+            if c == '\n' {
+                newlines += 1;
+                self.next();
+            } else if c.is_whitespace() {
+                self.next();
+            } else if c == '/' && self.lookahead2() == '/' {
+                out.push(self.scan_line_comment(newlines >= 2));
+                newlines = 0;
+            } else if c == '/' && self.lookahead2() == '*' {
+                out.push(self.scan_block_comment(newlines >= 2));
+                newlines = 0;
+            } else {
+                break;
+            }
+        }
+
+        out
+    }
+
+    /// Collect comments that sit on the same line as the token just scanned,
+    /// stopping at the first newline or real token.
+    fn collect_trailing_trivia(&mut self) -> Vec<Token> {
+        let mut out = Vec::new();
+
+        loop {
+            let c = self.lookahead();
+            if c == '\n' || c == '\0' {
+                break;
+            }
+
+            if c.is_whitespace() {
+                self.next();
+            } else if c == '/' && self.lookahead2() == '/' {
+                // A line comment runs to end of line, so nothing can trail it.
+                out.push(self.scan_line_comment(false));
+                break;
+            } else if c == '/' && self.lookahead2() == '*' {
+                out.push(self.scan_block_comment(false));
+            } else {
+                break;
+            }
+        }
+
+        out
+    }
+
+    /// Scan a `// ...` comment (the cursor sits on the leading `/`).
+    fn scan_line_comment(&mut self, isolated: bool) -> Token {
+        self.start = self.bytes;
+        let mut text = String::new();
+        text.push(self.next().unwrap()); // '/'
+        text.push(self.next().unwrap()); // '/'
+
+        while self.lookahead() != '\n' && !self.eof() {
+            text.push(self.next().unwrap());
+        }
+
+        let mut tok = self.make_token(TokenType::LineComment, &text);
+        tok.isolated = isolated;
+        tok
+    }
+
+    /// Scan a `/* ... */` comment (the cursor sits on the leading `/`).
+    fn scan_block_comment(&mut self, isolated: bool) -> Token {
+        self.start = self.bytes;
+        let mut text = String::new();
+        text.push(self.next().unwrap()); // '/'
+        text.push(self.next().unwrap()); // '*'
+
+        // The loop consumes through the closing `*`; only the final `/`
+        // remains to be taken once it exits.
+        while !(self.eof() || self.curr() == '*' && self.lookahead() == '/') {
+            text.push(self.next().unwrap());
+        }
+        if !self.eof() {
+            text.push(self.next().unwrap()); // '/'
+        }
+
+        let mut tok = self.make_token(TokenType::BlockComment, &text);
+        tok.isolated = isolated;
+        tok
+    }
+
     fn curr(&self) -> char {
         self.current
     }
 
     fn make_token(&self, ty: TokenType, text: &str) -> Token {
-        Token::new(ty, text, self.line, self.column)
+        Token::new(ty, text, self.line, self.column, self.start, self.bytes - self.start)
+    }
+
+    /// Build an error token spanning the text scanned since the start of the
+    /// current token. Lexing is never aborted: the caller emits this token and
+    /// keeps scanning from wherever the cursor was left.
+    fn error_token(&self, kind: LexErrorKind) -> Token {
+        Token::new(
+            TokenType::Error(kind),
+            "",
+            self.line,
+            self.column,
+            self.start,
+            self.bytes - self.start,
+        )
+    }
+
+    /// Build an error token for a bad escape, translating the offset `off`
+    /// (relative to the literal's content) into a span that starts at the
+    /// offending escape rather than at the opening quote and runs to the end
+    /// of the literal. `self.start + 1` skips the opening quote.
+    fn bad_escape(&self, e: UnescapeError, off: usize) -> Token {
+        let start = self.start + 1 + off;
+        Token::new(
+            TokenType::Error(LexErrorKind::BadEscape(e)),
+            "",
+            self.line,
+            self.column,
+            start,
+            self.bytes.saturating_sub(start),
+        )
     }
 }
 
@@ -285,6 +500,12 @@ pub struct Lexer<'a> {
     pub column: usize,
     src: Chars<'a>,
     current: char,
+    /// Running byte offset of the cursor into the original source.
+    bytes: usize,
+    /// Byte offset at which the token currently being scanned begins.
+    start: usize,
+    /// Whether comments are preserved as trivia.
+    trivia: bool,
 }
 
 impl Iterator for Lexer<'_> {
@@ -292,28 +513,135 @@ impl Iterator for Lexer<'_> {
     fn next(&mut self) -> Option<Self::Item> {
         let c = self.src.next();
 
-        self.current = c.unwrap();
-
-        self.column += 1;
-        if c == Some('\n') {
-            self.line += 1;
-            self.column = 0;
+        if let Some(ch) = c {
+            self.current = ch;
+            self.bytes += ch.len_utf8();
+            self.column += 1;
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 0;
+            }
         }
         c
     }
 }
 
+impl<'a> Lexer<'a> {
+    /// Drive the lexer to exhaustion and yield [`Token`]s instead of raw
+    /// characters. The returned iterator stops after the first
+    /// [`TokenType::Eof`], so `lexer.into_tokens().collect::<Vec<_>>()` gives
+    /// the complete token list ready for backtracking.
+    pub fn into_tokens(self) -> Tokens<'a> {
+        Tokens { lexer: self, done: false }
+    }
+}
+
+/// An iterator adaptor over a [`Lexer`] that yields [`Token`]s by repeatedly
+/// calling [`Lexer::next_token`], terminating after the first
+/// [`TokenType::Eof`].
+pub struct Tokens<'a> {
+    lexer: Lexer<'a>,
+    done: bool,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let tok = self.lexer.next_token();
+        if tok.ty == TokenType::Eof {
+            self.done = true;
+        }
+        Some(tok)
+    }
+}
+
+/// A fully-lexed token buffer supporting arbitrary multi-token lookahead.
+///
+/// The whole input is tokenized up front (including the trailing
+/// [`TokenType::Eof`]) so a parser can `peek`, `peek_nth`, and `bump` freely
+/// and backtrack without ever re-running the lexer.
+pub struct TokenStream {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl TokenStream {
+    /// Lex `code` in full and build a stream positioned at the first token.
+    pub fn new(code: &str) -> TokenStream {
+        TokenStream::from_lexer(Lexer::new(code))
+    }
+
+    /// Build a stream from an already-configured [`Lexer`].
+    pub fn from_lexer(lexer: Lexer) -> TokenStream {
+        TokenStream { tokens: lexer.into_tokens().collect(), pos: 0 }
+    }
+
+    /// The token at the current position, or `None` once the stream is spent.
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// The token `n` positions ahead of the cursor (`peek_nth(0)` == `peek`).
+    pub fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n)
+    }
+
+    /// Return the current token and advance the cursor past it.
+    pub fn bump(&mut self) -> Option<&Token> {
+        if self.pos < self.tokens.len() {
+            let idx = self.pos;
+            self.pos += 1;
+            Some(&self.tokens[idx])
+        } else {
+            None
+        }
+    }
+
+    /// Consume the stream, returning the underlying token list.
+    pub fn into_vec(self) -> Vec<Token> {
+        self.tokens
+    }
+}
+
 /// The minimal lexeme of the code.
 pub struct Token {
     pub ty: TokenType,
     pub text: String,
     pub line: usize,
     pub column: usize,
+    /// Byte offset of the token's first character in the original source.
+    pub start: usize,
+    /// Length of the token in bytes, so `&source[start..start + len]` slices
+    /// the exact original lexeme.
+    pub len: usize,
+    /// Comments immediately preceding this token, populated only in
+    /// comment-preserving mode.
+    pub leading: Vec<Token>,
+    /// Comments on the same line following this token, populated only in
+    /// comment-preserving mode.
+    pub trailing: Vec<Token>,
+    /// For a comment token, whether it was preceded by a blank line.
+    pub isolated: bool,
 }
 
 impl Token {
-    pub fn new(ty: TokenType, text: &str, line: usize, column: usize) -> Token {
-        Token { ty, text: text.into(), line, column }
+    pub fn new(ty: TokenType, text: &str, line: usize, column: usize, start: usize, len: usize) -> Token {
+        Token {
+            ty,
+            text: text.into(),
+            line,
+            column,
+            start,
+            len,
+            leading: Vec::new(),
+            trailing: Vec::new(),
+            isolated: false,
+        }
     }
 }
 
@@ -324,6 +652,7 @@ pub enum TokenType {
     Id,
     // literals
     IntLiteral,
+    BigIntLiteral,
     BoolLiteral,
     StringLiteral,
     CharLiteral,
@@ -343,11 +672,33 @@ pub enum TokenType {
     RBracket,      // ]
     LBrace,        // {
     RBrace,        // }
+    // Trivia (comment-preserving mode only)
+    LineComment,
+    BlockComment,
     // Special tokens
-    Error,
+    Error(LexErrorKind),
     Eof,
 }
 
+/// The reason a token failed to lex. Stored as a flag on the error token
+/// (rather than as a pre-formatted message) so lexing stays decoupled from
+/// diagnostic rendering.
+#[derive(Debug, Eq, PartialEq)]
+pub enum LexErrorKind {
+    /// A string literal was not closed before end of input.
+    UnterminatedString,
+    /// A char literal was not closed before end of input.
+    UnterminatedChar,
+    /// A numeric literal was malformed (e.g. a stray digit separator).
+    InvalidNumber,
+    /// A numeric literal carried an invalid suffix (e.g. `1.0n`).
+    InvalidNumberSuffix,
+    /// An unknown character that begins no valid token.
+    UnknownChar,
+    /// An escape sequence inside a string or char literal was invalid.
+    BadEscape(super::unescape::UnescapeError),
+}
+
 impl Debug for Token {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write(f, format_args!("Lexeme: (Type: {:?}, Content: {}, At: (L: {}, C, {}))", 
@@ -360,11 +711,14 @@ impl Debug for TokenType {
         match self {
             TokenType::Id => write!(f, "<identifier>"),
             TokenType::IntLiteral => write!(f, "<literal: int>"),
+            TokenType::BigIntLiteral => write!(f, "<literal: bigint>"),
             TokenType::BoolLiteral => write!(f, "<literal: bool>"),
             TokenType::StringLiteral => write!(f, "<literal: string>"),
             TokenType::CharLiteral => write!(f, "<literal: char>"),
             TokenType::FloatLiteral => write!(f, "<literal: float>"),
             TokenType::Keyword => write!(f, "keyword"),
+            TokenType::LineComment => write!(f, "<comment: line>"),
+            TokenType::BlockComment => write!(f, "<comment: block>"),
             TokenType::Eof => write!(f, "EOF"),
             TokenType::Op => write!(f, "<operator>"),
             TokenType::LParen => write!(f, "<punctuation>"),
@@ -373,7 +727,7 @@ impl Debug for TokenType {
             TokenType::RBracket => write!(f, "<punctuation>"),
             TokenType::LBrace => write!(f, "<punctuation>"),
             TokenType::RBrace => write!(f, "<punctuation>"),
-            TokenType::Error => write!(f, "<error msg>"),
+            TokenType::Error(kind) => write!(f, "<error: {:?}>", kind),
         }
     }
 }
\ No newline at end of file
@@ -38,21 +38,31 @@ impl Display for UnescapeError {
     }
 }
 
-pub fn unescape(input: &str) -> Result<String, UnescapeError> {
+/// Unescape `input`, returning the decoded string. On failure the error is
+/// paired with the byte offset within `input` at which the offending escape
+/// begins, so a caller can map it to a precise source span.
+pub fn unescape(input: &str) -> Result<String, (UnescapeError, usize)> {
     let mut que = input.chars().collect::<VecDeque<char>>();
     let mut res: String = String::new();
+    let mut pos = 0usize;
 
     if input.is_empty() {
         return Ok(res);
     }
 
-    while let Some(c) = que.pop_front() {
+    loop {
+        let esc_start = pos;
+        let c = match pop(&mut que, &mut pos) {
+            Some(c) => c,
+            None => break,
+        };
+
         if c != '\\' {
             res.push(c);
             continue;
         }
 
-        let esc = que.pop_front().ok_or(OnlyOneSlashError)?;
+        let esc = pop(&mut que, &mut pos).ok_or((OnlyOneSlashError, esc_start))?;
         match esc {
             'b' => res.push('\u{0008}'),
             'r' => res.push('\r'),
@@ -61,65 +71,109 @@ pub fn unescape(input: &str) -> Result<String, UnescapeError> {
             '\'' => res.push('\''),
             '\\' => res.push('\\'),
             'u' => {
-                if que.is_empty() || !que.iter().any(|&c| c == '}'){
-                    return Err(UnclosedUnicode);
-                }
-
-                if que.pop_front().unwrap() != '{' {
-                    return Err(IllegalUnicode);
-                }
-
-                let mut digits: u32 = 0;
-                let mut value: u32 = 0;
-
-                while let Some(x) = que.pop_front() {
-                    if digits > 6 {
-                        return Err(TooLongUnicode);
-                    }
-                    
-                    if x == '}' {
-                        if digits == 0 {
-                            return Err(EmptyUnicode);
-                        }
-                        
-                        if value > 0x10FFFF {
-                            return Err(ValueOutOfUnicode);
+                let value = unicode_escape(&mut que, &mut pos, esc_start)?;
+
+                if (0xD800..=0xDBFF).contains(&value) {
+                    // High surrogate: fold a following `\u{...}` low surrogate
+                    // into a single astral-plane scalar, as boa does.
+                    if que.front() == Some(&'\\') && que.get(1) == Some(&'u') {
+                        let low_start = pos;
+                        pop(&mut que, &mut pos); // '\\'
+                        pop(&mut que, &mut pos); // 'u'
+                        let low = unicode_escape(&mut que, &mut pos, low_start)?;
+
+                        if !(0xDC00..=0xDFFF).contains(&low) {
+                            return Err((IllegalSurrogate, low_start));
                         }
-                        
-                        let ch = char::from_u32(value).ok_or(IllegalSurrogate)?;
-                        res.push(ch);
-                        break;
-                    }
-                    
-                    if !x.is_ascii_hexdigit() {
-                        return Err(InvalidCharInUnicode);
+
+                        let cp = 0x10000 + ((value - 0xD800) << 10) + (low - 0xDC00);
+                        res.push(char::from_u32(cp).ok_or((IllegalSurrogate, esc_start))?);
+                    } else {
+                        return Err((IllegalSurrogate, esc_start));
                     }
-                    
-                    digits += 1;
-                    value = (value << 4) | x.to_digit(16).unwrap();
+                } else if (0xDC00..=0xDFFF).contains(&value) {
+                    // A lone low surrogate is never valid.
+                    return Err((IllegalSurrogate, esc_start));
+                } else {
+                    res.push(char::from_u32(value).ok_or((IllegalSurrogate, esc_start))?);
                 }
-                
             }
 
             'x' => {
-                let high = que.pop_front().ok_or(TooShortEscape)?;
-                let high = high.to_digit(16).ok_or(InvalidCharInHex)?;
+                let high = pop(&mut que, &mut pos).ok_or((TooShortEscape, esc_start))?;
+                let high = high.to_digit(16).ok_or((InvalidCharInHex, esc_start))?;
 
-                let low = que.pop_front().ok_or(TooShortEscape)?;
-                let low = low.to_digit(16).ok_or(InvalidCharInHex)?;
+                let low = pop(&mut que, &mut pos).ok_or((TooShortEscape, esc_start))?;
+                let low = low.to_digit(16).ok_or((InvalidCharInHex, esc_start))?;
 
                 let val = high * 16 + low;
 
                 if val > 0x7f {
-                    return Err(ValueOutOfHex);
+                    return Err((ValueOutOfHex, esc_start));
                 }
 
                 res.push(val as u8 as char);
             }
 
-            _ => return Err(IllegalEscape),
+            _ => return Err((IllegalEscape, esc_start)),
         }
     }
 
     Ok(res)
+}
+
+/// Pop the next char off `que`, advancing the running byte offset `pos`.
+fn pop(que: &mut VecDeque<char>, pos: &mut usize) -> Option<char> {
+    let c = que.pop_front();
+    if let Some(ch) = c {
+        *pos += ch.len_utf8();
+    }
+    c
+}
+
+/// Parse the `{hex}` body of a `\u{...}` escape (the leading `\u` is already
+/// consumed), returning its scalar value. `start` is the byte offset of the
+/// whole escape, used to locate any error.
+fn unicode_escape(
+    que: &mut VecDeque<char>,
+    pos: &mut usize,
+    start: usize,
+) -> Result<u32, (UnescapeError, usize)> {
+    if que.is_empty() || !que.iter().any(|&c| c == '}') {
+        return Err((UnclosedUnicode, start));
+    }
+
+    if pop(que, pos).unwrap() != '{' {
+        return Err((IllegalUnicode, start));
+    }
+
+    let mut digits: u32 = 0;
+    let mut value: u32 = 0;
+
+    while let Some(x) = pop(que, pos) {
+        if digits > 6 {
+            return Err((TooLongUnicode, start));
+        }
+
+        if x == '}' {
+            if digits == 0 {
+                return Err((EmptyUnicode, start));
+            }
+
+            if value > 0x10FFFF {
+                return Err((ValueOutOfUnicode, start));
+            }
+
+            return Ok(value);
+        }
+
+        if !x.is_ascii_hexdigit() {
+            return Err((InvalidCharInUnicode, start));
+        }
+
+        digits += 1;
+        value = (value << 4) | x.to_digit(16).unwrap();
+    }
+
+    Err((UnclosedUnicode, start))
 }
\ No newline at end of file